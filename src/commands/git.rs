@@ -1,6 +1,7 @@
 use crate::{CliResult, Command};
 use clap::Subcommand;
 use colored::Colorize;
+use git2::{BranchType, Repository};
 use inquire::{Confirm, InquireError, Select};
 use std::process;
 use which::which;
@@ -11,34 +12,102 @@ use crate::CliError;
 #[derive(Subcommand)]
 pub enum GitCommands {
     #[command(about = "sync latest changes from remote")]
-    Sync {},
+    Sync {
+        #[arg(long, help = "abort instead of auto-stashing local changes")]
+        no_stash: bool,
+    },
     #[command(about = "switch branch (local only)")]
-    Switch {},
+    Switch {
+        #[arg(long, help = "abort instead of auto-stashing local changes")]
+        no_stash: bool,
+    },
     #[command(about = "delete branch (local only)")]
     Delete {},
+    #[command(about = "show a compact working-tree status summary")]
+    Status {},
+    #[command(about = "meld working changes into an existing commit")]
+    Amend {
+        #[arg(long, help = "print what would change without touching the repo")]
+        dry_run: bool,
+    },
 }
 
 // map 'git' subcommands to functions
 impl Command for GitCommands {
     fn execute(&self) -> CliResult<()> {
         match self {
-            GitCommands::Sync {} => sync(),
-            GitCommands::Switch {} => switch(),
+            GitCommands::Sync { no_stash } => sync(*no_stash),
+            GitCommands::Switch { no_stash } => switch(*no_stash),
             GitCommands::Delete {} => delete(),
+            GitCommands::Status {} => status(),
+            GitCommands::Amend { dry_run } => amend(*dry_run),
         }
     }
 }
 
-// run git command with arguments
+// supported version control backends
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Backend {
+    Git,
+    Mercurial,
+    Jujutsu,
+}
+
+impl Backend {
+    // detect the backend in use by walking up from the current directory
+    fn detect() -> Option<Backend> {
+        let mut dir = std::env::current_dir().ok()?;
+        loop {
+            if dir.join(".jj").is_dir() {
+                return Some(Backend::Jujutsu);
+            }
+            if dir.join(".hg").is_dir() {
+                return Some(Backend::Mercurial);
+            }
+            if dir.join(".git").exists() {
+                return Some(Backend::Git);
+            }
+            if !dir.pop() {
+                return None;
+            }
+        }
+    }
+
+    // binary used to invoke this backend
+    fn binary(&self) -> &'static str {
+        match self {
+            Backend::Git => "git",
+            Backend::Mercurial => "hg",
+            Backend::Jujutsu => "jj",
+        }
+    }
+
+    // name used in user-facing messages
+    fn name(&self) -> &'static str {
+        match self {
+            Backend::Git => "git",
+            Backend::Mercurial => "mercurial",
+            Backend::Jujutsu => "jujutsu",
+        }
+    }
+
+    // whether this backend has a stash/shelve concept for parking local changes
+    fn supports_stash(&self) -> bool {
+        !matches!(self, Backend::Jujutsu)
+    }
+}
+
+// run a vcs command with arguments
 //
 // errors:
-// - CliError::Command: if the git command fails
-fn git_exec(
+// - CliError::Command: if the command fails
+fn vcs_exec(
+    backend: Backend,
     args: &[&str],
     error_msg: &str,
     capture_output: bool,
 ) -> Result<process::Output, CliError> {
-    let mut cmd = process::Command::new("git");
+    let mut cmd = process::Command::new(backend.binary());
     cmd.args(args);
 
     if capture_output {
@@ -57,126 +126,386 @@ fn git_exec(
     }
 }
 
-// get current branch name and list of all branches
+// run a read-only git query. disables core.fsmonitor so a configured
+// fsmonitor hook never runs an untrusted program, and sets
+// GIT_OPTIONAL_LOCKS=0 so the query never takes or churns the index lock
 //
-// panics: if git is not installed
 // errors:
-// - CliError::Command: if any git command fails
-fn get_branch_info() -> CliResult<(String, Vec<String>)> {
-    which("git").expect("git not found. install git and try again.");
+// - CliError::Command: if the git command fails
+fn git_query(args: &[&str], error_msg: &str) -> CliResult<process::Output> {
+    let mut query_args = vec!["-c", "core.fsmonitor="];
+    query_args.extend_from_slice(args);
+
+    process::Command::new(Backend::Git.binary())
+        .args(&query_args)
+        .env("GIT_OPTIONAL_LOCKS", "0")
+        .output()
+        .map_err(|e| CliError::Command(format!("{}: {}", error_msg, e)))
+}
 
-    let git_output = git_exec(
-        &["--no-pager", "branch", "--no-color"],
-        "failed to get branch list",
-        true,
-    )?;
+// outcome of checking a repo against its configured remote
+enum SyncStatus {
+    NoRemote,
+    Pending,
+}
 
-    let git_output_str = String::from_utf8_lossy(&git_output.stdout);
-    let all_branches = git_output_str
-        .lines()
-        .map(|line| line.trim())
-        .collect::<Vec<&str>>();
-
-    // finds current branch from the above git command output
-    // if no branch is found, defaults to 'main'
-    let current_branch = all_branches
-        .iter()
-        .find(|branch| branch.starts_with('*'))
-        .map(|branch| branch.trim_start_matches('*').trim())
-        .unwrap_or("main");
-
-    let other_branches = all_branches
-        .iter()
-        .filter(|branch| !branch.starts_with('*'))
-        .map(|branch| branch.trim())
-        .collect::<Vec<&str>>();
-
-    Ok((
-        current_branch.to_string(),
-        other_branches.iter().map(|s| s.to_string()).collect(),
-    ))
+// read-only queries over a vcs repository. git answers these through libgit2
+// (no subprocess per query); other backends still go through their cli since
+// there is no equivalent in-process library available for them.
+trait VcsQuery {
+    // current branch name and the list of other local branches
+    fn branch_info(&self) -> CliResult<(String, Vec<String>)>;
+    // whether the current branch has a remote worth syncing against
+    fn sync_status(&self) -> CliResult<SyncStatus>;
 }
 
-// sync latest changes from remote branch
+impl Backend {
+    // open a read-only query handle for this backend
+    fn query(&self) -> CliResult<Box<dyn VcsQuery>> {
+        match self {
+            Backend::Git => {
+                let repo = Repository::discover(".")
+                    .map_err(|e| CliError::Command(format!("failed to open git repository: {}", e)))?;
+                Ok(Box::new(GitQuery(repo)))
+            }
+            Backend::Mercurial | Backend::Jujutsu => Ok(Box::new(CliQuery(*self))),
+        }
+    }
+}
+
+// git2-backed queries: no process spawned, no porcelain text to parse
+struct GitQuery(Repository);
+
+impl VcsQuery for GitQuery {
+    fn branch_info(&self) -> CliResult<(String, Vec<String>)> {
+        let head = self.0.head().ok();
+        let current_branch = head
+            .as_ref()
+            .and_then(|head| head.shorthand())
+            .unwrap_or("HEAD")
+            .to_string();
+
+        let mut other_branches = Vec::new();
+        let branches = self
+            .0
+            .branches(Some(BranchType::Local))
+            .map_err(|e| CliError::Command(format!("failed to list branches: {}", e)))?;
+        for branch in branches {
+            let (branch, _) =
+                branch.map_err(|e| CliError::Command(format!("failed to read branch: {}", e)))?;
+            let name = branch
+                .name()
+                .map_err(|e| CliError::Command(format!("failed to read branch name: {}", e)))?;
+            if let Some(name) = name {
+                if name != current_branch {
+                    other_branches.push(name.to_string());
+                }
+            }
+        }
+
+        Ok((current_branch, other_branches))
+    }
+
+    fn sync_status(&self) -> CliResult<SyncStatus> {
+        let head = match self.0.head() {
+            Ok(head) => head,
+            Err(_) => return Ok(SyncStatus::NoRemote),
+        };
+        if !head.is_branch() {
+            return Ok(SyncStatus::NoRemote);
+        }
+
+        let branch = git2::Branch::wrap(head);
+        match branch.upstream() {
+            // the local tracking ref is only as fresh as the last fetch, so it
+            // can't tell us whether there's anything new to pull - only that a
+            // remote is configured. always let 'fetch' + 'pull --rebase' run
+            // and no-op if there was nothing to do, like the plain git cli does.
+            Ok(_) => Ok(SyncStatus::Pending),
+            Err(_) => Ok(SyncStatus::NoRemote),
+        }
+    }
+}
+
+// cli-backed queries, used by backends without a bundled rust library
+struct CliQuery(Backend);
+
+impl VcsQuery for CliQuery {
+    fn branch_info(&self) -> CliResult<(String, Vec<String>)> {
+        match self.0 {
+            Backend::Mercurial => {
+                // mercurial bookmarks behave like git's local branches: one marked
+                // current with '*', movable with 'hg update', deletable with 'hg bookmark -d'
+                let output = vcs_exec(self.0, &["bookmarks"], "failed to get branch list", true)?;
+                let output_str = String::from_utf8_lossy(&output.stdout);
+                let all_bookmarks = output_str
+                    .lines()
+                    .map(|line| line.trim())
+                    .collect::<Vec<&str>>();
+
+                let current_branch = all_bookmarks
+                    .iter()
+                    .find(|line| line.starts_with('*'))
+                    .and_then(|line| line.trim_start_matches('*').trim().split_whitespace().next())
+                    .unwrap_or("default")
+                    .to_string();
+
+                let other_branches = all_bookmarks
+                    .iter()
+                    .filter(|line| !line.is_empty() && !line.starts_with('*'))
+                    .filter_map(|line| line.split_whitespace().next())
+                    .map(|name| name.to_string())
+                    .collect();
+
+                Ok((current_branch, other_branches))
+            }
+            Backend::Jujutsu => {
+                let output = vcs_exec(self.0, &["branch", "list"], "failed to get branch list", true)?;
+                let output_str = String::from_utf8_lossy(&output.stdout);
+                let all_branches: Vec<String> = output_str
+                    .lines()
+                    .filter_map(|line| line.split(':').next())
+                    .map(|name| name.trim().to_string())
+                    .filter(|name| !name.is_empty())
+                    .collect();
+
+                let current_output = vcs_exec(
+                    self.0,
+                    &["log", "-r", "@", "--no-graph", "-T", "bookmarks"],
+                    "failed to get current bookmark",
+                    true,
+                )?;
+                let current_branch = String::from_utf8_lossy(&current_output.stdout)
+                    .trim()
+                    .trim_end_matches(',')
+                    .to_string();
+                let current_branch = if current_branch.is_empty() {
+                    "@".to_string()
+                } else {
+                    current_branch
+                };
+
+                let other_branches = all_branches
+                    .into_iter()
+                    .filter(|name| *name != current_branch)
+                    .collect();
+
+                Ok((current_branch, other_branches))
+            }
+            Backend::Git => unreachable!("git uses GitQuery"),
+        }
+    }
+
+    fn sync_status(&self) -> CliResult<SyncStatus> {
+        match self.0 {
+            Backend::Mercurial => {
+                let output = vcs_exec(self.0, &["paths", "default"], "failed to get default path", true)?;
+                if output.status.success() && !output.stdout.is_empty() {
+                    Ok(SyncStatus::Pending)
+                } else {
+                    Ok(SyncStatus::NoRemote)
+                }
+            }
+            Backend::Jujutsu => {
+                let output = vcs_exec(self.0, &["git", "remote", "list"], "failed to get git remotes", true)?;
+                if !output.stdout.is_empty() {
+                    Ok(SyncStatus::Pending)
+                } else {
+                    Ok(SyncStatus::NoRemote)
+                }
+            }
+            Backend::Git => unreachable!("git uses GitQuery"),
+        }
+    }
+}
+
+// whether the working copy has local changes to stash before a destructive op
 //
-// panics: if git is not installed
+// errors:
+// - CliError::Command: if the vcs command fails
+fn has_local_changes(backend: Backend) -> CliResult<bool> {
+    match backend {
+        Backend::Git => {
+            let output = git_query(&["status", "--porcelain"], "failed to get status")?;
+            Ok(!output.stdout.is_empty())
+        }
+        Backend::Mercurial => {
+            let output = vcs_exec(backend, &["status"], "failed to get status", true)?;
+            Ok(!output.stdout.is_empty())
+        }
+        // jj commits the working copy automatically, so there is nothing to stash
+        Backend::Jujutsu => Ok(false),
+    }
+}
+
+// park local changes, including untracked files, so a sync or switch can
+// proceed on a clean tree. unlike staging everything first, this keeps the
+// staged/unstaged split intact for stash_pop to reconstruct
 //
 // errors:
-// - CliError::Command: if any git command fails
-pub fn sync() -> CliResult<()> {
-    which("git").expect("git not found. install git and try again.");
+// - CliError::Command: if the vcs command fails
+fn stash_push(backend: Backend) -> CliResult<()> {
+    match backend {
+        Backend::Git => {
+            vcs_exec(
+                backend,
+                &["stash", "push", "--include-untracked"],
+                "failed to stash local changes",
+                false,
+            )?;
+        }
+        Backend::Mercurial => {
+            vcs_exec(backend, &["shelve"], "failed to shelve local changes", false)?;
+        }
+        Backend::Jujutsu => {}
+    }
 
-    let git_check = git_exec(
-        &["rev-parse", "--git-dir"],
-        "failed to execute git command",
-        true,
-    )?;
-    if !git_check.status.success() {
-        println!("current directory is not a git repository. nothing to sync.");
-        return Ok(());
+    Ok(())
+}
+
+// restore changes parked by stash_push, reconstructing the original
+// staged/unstaged split automatically
+//
+// errors:
+// - CliError::Command: if the vcs command fails
+fn stash_pop(backend: Backend) -> CliResult<()> {
+    match backend {
+        Backend::Git => {
+            vcs_exec(backend, &["stash", "pop", "--index"], "failed to restore local changes", false)?;
+        }
+        Backend::Mercurial => {
+            vcs_exec(backend, &["unshelve"], "failed to restore local changes", false)?;
+        }
+        Backend::Jujutsu => {}
     }
 
-    let remote_status = git_exec(
-        &["rev-parse", "--abbrev-ref", "--symbolic-full-name", "@{u}"],
-        "failed to get upstream branch",
-        true,
-    )?;
-    if !remote_status.status.success() {
-        println!("no upstream branch found. nothing to sync");
-        return Ok(());
+    Ok(())
+}
+
+// check if the current directory is inside a git repository
+//
+// errors:
+// - CliError::Command: if the git command fails
+fn is_git_repo() -> CliResult<bool> {
+    let git_check = git_query(&["rev-parse", "--git-dir"], "failed to execute git command")?;
+
+    Ok(git_check.status.success())
+}
+
+// sync latest changes from remote branch
+//
+// panics: if the detected backend is not installed
+//
+// errors:
+// - CliError::Command: if any vcs command fails
+pub fn sync(no_stash: bool) -> CliResult<()> {
+    let backend = match Backend::detect() {
+        Some(backend) => backend,
+        None => {
+            println!("current directory is not a git, mercurial, or jujutsu repository. nothing to sync.");
+            return Ok(());
+        }
+    };
+    which(backend.binary())
+        .unwrap_or_else(|_| panic!("{} not found. install {} and try again.", backend.binary(), backend.name()));
+
+    match backend.query()?.sync_status()? {
+        SyncStatus::NoRemote => {
+            println!("no remote found. nothing to sync.");
+            return Ok(());
+        }
+        SyncStatus::Pending => {}
     }
 
     println!("{}", "checking local branch status".bold());
     let mut local_changes_stashed = false;
-    let git_status = git_exec(&["status", "--porcelain"], "failed to get git status", true)?;
-    if !git_status.stdout.is_empty() {
+    if backend.supports_stash() && has_local_changes(backend)? {
+        if no_stash {
+            println!("local changes found and --no-stash set. aborting sync.");
+            return Ok(());
+        }
         println!("- local changes found. stashing local changes");
-        git_exec(&["add", "."], "failed to stage local changes", false)?;
-        git_exec(&["stash"], "failed to stash local changes", false)?;
+        stash_push(backend)?;
         local_changes_stashed = true;
     }
 
     println!("{}", "syncing changes with upstream branch".bold());
-    git_exec(&["fetch", "-p"], "failed to fetch remote changes", false)?;
-    git_exec(
-        &["pull", "--rebase"],
-        "failed to pull remote changes",
-        false,
-    )?;
+    match backend {
+        Backend::Git => {
+            vcs_exec(backend, &["fetch", "-p"], "failed to fetch remote changes", false)?;
+            vcs_exec(backend, &["pull", "--rebase"], "failed to pull remote changes", false)?;
+        }
+        Backend::Mercurial => {
+            vcs_exec(backend, &["pull", "--update"], "failed to pull remote changes", false)?;
+        }
+        Backend::Jujutsu => {
+            vcs_exec(backend, &["git", "fetch"], "failed to fetch remote changes", false)?;
+            vcs_exec(backend, &["edit", "@"], "failed to update working copy", false)?;
+        }
+    }
 
-    let git_log_output = git_exec(
-        &["log", "-1", "--oneline"],
-        "failed to get latest commit",
-        true,
-    )?;
-    let latest_commit = String::from_utf8_lossy(&git_log_output.stdout)
-        .trim()
-        .to_string();
+    let latest_commit = match backend {
+        Backend::Git => {
+            let output = git_query(&["log", "-1", "--oneline"], "failed to get latest commit")?;
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        }
+        Backend::Mercurial => {
+            let output = vcs_exec(
+                backend,
+                &["log", "-l", "1", "--template", "{node|short} {desc|firstline}\n"],
+                "failed to get latest commit",
+                true,
+            )?;
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        }
+        Backend::Jujutsu => {
+            let output = vcs_exec(
+                backend,
+                &[
+                    "log",
+                    "-r",
+                    "@",
+                    "--no-graph",
+                    "-T",
+                    "commit_id.short() ++ \" \" ++ description.first_line()",
+                ],
+                "failed to get latest commit",
+                true,
+            )?;
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        }
+    };
 
     println!("- latest commit: {}", latest_commit.dimmed());
 
     if local_changes_stashed {
         println!("{}", "restoring stashed changes".bold());
-        git_exec(&["stash", "pop"], "failed to restore local changes", false)?;
-        git_exec(&["stash", "clear"], "failed to clear stash", false)?;
-
-        println!("{}", "unstaging local changes.".bold());
-        git_exec(&["reset"], "failed to unstage local changes", false)?;
+        stash_pop(backend)?;
     }
 
-    println!("{}", "git sync complete ^.^".bold());
+    println!("{}", "sync complete ^.^".bold());
 
     Ok(())
 }
 
 // switch local branch
 //
-// panics: if git is not installed
+// panics: if the detected backend is not installed
 //
 // errors:
-// - CliError::Command: if any git command fails
-pub fn switch() -> CliResult<()> {
-    let (current_branch, other_branches) = get_branch_info()?;
+// - CliError::Command: if any vcs command fails
+pub fn switch(no_stash: bool) -> CliResult<()> {
+    let backend = match Backend::detect() {
+        Some(backend) => backend,
+        None => {
+            println!("current directory is not a git, mercurial, or jujutsu repository. nothing to switch.");
+            return Ok(());
+        }
+    };
+    which(backend.binary())
+        .unwrap_or_else(|_| panic!("{} not found. install {} and try again.", backend.binary(), backend.name()));
+
+    let (current_branch, other_branches) = backend.query()?.branch_info()?;
 
     // check if other_branches is empty
     // if empty, return early
@@ -208,23 +537,31 @@ pub fn switch() -> CliResult<()> {
 
     println!("{}", "checking local branch status".bold());
     let mut local_changes_stashed = false;
-    let git_status = git_exec(&["status", "--porcelain"], "failed to get git status", true)?;
-    if !git_status.stdout.is_empty() {
+    if backend.supports_stash() && has_local_changes(backend)? {
+        if no_stash {
+            println!("local changes found and --no-stash set. aborting switch.");
+            return Ok(());
+        }
         println!("- local changes found. stashing local changes");
-        git_exec(&["add", "."], "failed to stage local changes", false)?;
-        git_exec(&["stash"], "failed to stash local changes", false)?;
+        stash_push(backend)?;
         local_changes_stashed = true;
     }
 
-    git_exec(&["checkout", &new_branch], "failed to switch branch", false)?;
+    match backend {
+        Backend::Git => {
+            vcs_exec(backend, &["checkout", &new_branch], "failed to switch branch", false)?;
+        }
+        Backend::Mercurial => {
+            vcs_exec(backend, &["update", &new_branch], "failed to switch branch", false)?;
+        }
+        Backend::Jujutsu => {
+            vcs_exec(backend, &["edit", &new_branch], "failed to switch branch", false)?;
+        }
+    };
 
     if local_changes_stashed {
         println!("{}", "restoring stashed changes".bold());
-        git_exec(&["stash", "pop"], "failed to restore local changes", false)?;
-        git_exec(&["stash", "clear"], "failed to clear stash", false)?;
-
-        println!("{}", "unstaging local changes.".bold());
-        git_exec(&["reset"], "failed to unstage local changes", false)?;
+        stash_pop(backend)?;
     }
 
     println!("{}", "branch switch complete ^.^".bold());
@@ -234,12 +571,22 @@ pub fn switch() -> CliResult<()> {
 
 // delete a local branch
 //
-// panics: if git is not installed
+// panics: if the detected backend is not installed
 //
 // errors:
-// - CliError::Command: if any git command fails
+// - CliError::Command: if any vcs command fails
 pub fn delete() -> CliResult<()> {
-    let (current_branch, other_branches) = get_branch_info()?;
+    let backend = match Backend::detect() {
+        Some(backend) => backend,
+        None => {
+            println!("current directory is not a git, mercurial, or jujutsu repository. nothing to delete.");
+            return Ok(());
+        }
+    };
+    which(backend.binary())
+        .unwrap_or_else(|_| panic!("{} not found. install {} and try again.", backend.binary(), backend.name()));
+
+    let (current_branch, other_branches) = backend.query()?.branch_info()?;
 
     // check if other_branches is empty
     // if empty, return early
@@ -274,11 +621,17 @@ pub fn delete() -> CliResult<()> {
 
     match confirm {
         Ok(true) => {
-            git_exec(
-                &["branch", "-D", &branch_to_delete],
-                "failed to delete branch",
-                false,
-            )?;
+            match backend {
+                Backend::Git => {
+                    vcs_exec(backend, &["branch", "-D", &branch_to_delete], "failed to delete branch", false)?;
+                }
+                Backend::Mercurial => {
+                    vcs_exec(backend, &["bookmark", "-d", &branch_to_delete], "failed to delete branch", false)?;
+                }
+                Backend::Jujutsu => {
+                    vcs_exec(backend, &["branch", "delete", &branch_to_delete], "failed to delete branch", false)?;
+                }
+            };
 
             println!("{}", "branch delete complete ^.^".bold());
         }
@@ -294,3 +647,269 @@ pub fn delete() -> CliResult<()> {
 
     Ok(())
 }
+
+// tracks the working-tree counts parsed from 'git status --porcelain=v2 --branch'
+#[derive(Default)]
+struct StatusCounts {
+    staged: u32,
+    modified: u32,
+    renamed: u32,
+    deleted: u32,
+    untracked: u32,
+    conflicts: u32,
+}
+
+// show a compact, starship-style summary of the working tree
+//
+// panics: if git is not installed
+//
+// errors:
+// - CliError::Command: if any git command fails
+pub fn status() -> CliResult<()> {
+    which("git").expect("git not found. install git and try again.");
+
+    if !is_git_repo()? {
+        println!("current directory is not a git repository. nothing to show.");
+        return Ok(());
+    }
+
+    let git_status = git_query(
+        &["status", "--porcelain=v2", "--branch"],
+        "failed to get git status",
+    )?;
+    let git_status_str = String::from_utf8_lossy(&git_status.stdout);
+
+    let mut branch = String::from("HEAD");
+    let mut ahead: u32 = 0;
+    let mut behind: u32 = 0;
+    let mut counts = StatusCounts::default();
+
+    for line in git_status_str.lines() {
+        if let Some(head) = line.strip_prefix("# branch.head ") {
+            branch = head.to_string();
+        } else if let Some(ab) = line.strip_prefix("# branch.ab ") {
+            for field in ab.split_whitespace() {
+                if let Some(n) = field.strip_prefix('+') {
+                    ahead = n.parse().unwrap_or(0);
+                } else if let Some(n) = field.strip_prefix('-') {
+                    behind = n.parse().unwrap_or(0);
+                }
+            }
+        } else if let Some(rest) = line.strip_prefix("1 ").or_else(|| line.strip_prefix("2 ")) {
+            let xy = rest.split_whitespace().next().unwrap_or("");
+            let x = xy.chars().next().unwrap_or('.');
+            let y = xy.chars().nth(1).unwrap_or('.');
+
+            // index column (x) and worktree column (y) are evaluated independently,
+            // so e.g. 'MD' (staged modify, worktree delete) reports both
+            match x {
+                'R' => counts.renamed += 1,
+                'D' => counts.deleted += 1,
+                '.' => {}
+                _ => counts.staged += 1,
+            }
+            match y {
+                'R' => counts.renamed += 1,
+                'D' => counts.deleted += 1,
+                '.' => {}
+                _ => counts.modified += 1,
+            }
+        } else if line.starts_with("u ") {
+            counts.conflicts += 1;
+        } else if line.starts_with("? ") {
+            counts.untracked += 1;
+        }
+    }
+
+    let stash_output = git_query(&["stash", "list"], "failed to get stash list")?;
+    let stash_count = String::from_utf8_lossy(&stash_output.stdout)
+        .lines()
+        .filter(|line| !line.is_empty())
+        .count();
+
+    let mut segments: Vec<String> = vec![branch.cyan().bold().to_string()];
+
+    let ahead_behind = if ahead > 0 && behind > 0 {
+        "\u{21d5}".to_string()
+    } else if ahead > 0 {
+        format!("\u{21e1}{}", ahead)
+    } else if behind > 0 {
+        format!("\u{21e3}{}", behind)
+    } else {
+        "\u{2261}".to_string()
+    };
+    segments.push(ahead_behind.dimmed().to_string());
+
+    if counts.staged > 0 {
+        segments.push(format!("+{}", counts.staged).green().to_string());
+    }
+    if counts.modified > 0 {
+        segments.push(format!("!{}", counts.modified).yellow().to_string());
+    }
+    if counts.renamed > 0 {
+        segments.push(format!("\u{bb}{}", counts.renamed).blue().to_string());
+    }
+    if counts.deleted > 0 {
+        segments.push(format!("\u{2718}{}", counts.deleted).red().to_string());
+    }
+    if counts.untracked > 0 {
+        segments.push(
+            format!("?{}", counts.untracked)
+                .magenta()
+                .to_string(),
+        );
+    }
+    if counts.conflicts > 0 {
+        segments.push(
+            format!("={}", counts.conflicts)
+                .red()
+                .bold()
+                .to_string(),
+        );
+    }
+    if stash_count > 0 {
+        segments.push(format!("${}", stash_count).purple().to_string());
+    }
+
+    println!("{}", segments.join(" "));
+
+    Ok(())
+}
+
+// meld the current working changes into an existing commit, defaulting to
+// HEAD, rewriting any descendants via an autosquash rebase when an older
+// commit is chosen
+//
+// panics: if git is not installed
+//
+// errors:
+// - CliError::Command: if any git command fails
+pub fn amend(dry_run: bool) -> CliResult<()> {
+    which("git").expect("git not found. install git and try again.");
+
+    if !is_git_repo()? {
+        println!("current directory is not a git repository. nothing to amend.");
+        return Ok(());
+    }
+
+    if !has_local_changes(Backend::Git)? {
+        println!("no local changes found. nothing to amend.");
+        return Ok(());
+    }
+
+    let log_output = git_query(&["log", "--oneline", "-10"], "failed to get commit history")?;
+    let commits: Vec<String> = String::from_utf8_lossy(&log_output.stdout)
+        .lines()
+        .map(|line| line.to_string())
+        .collect();
+
+    if commits.is_empty() {
+        println!("no commits found. nothing to amend.");
+        return Ok(());
+    }
+
+    let target = match Select::new("select commit to amend into:", commits.clone()).prompt() {
+        Ok(commit) => commit,
+        Err(InquireError::OperationCanceled) => {
+            println!("{}", "aborting amend".bold());
+            return Ok(());
+        }
+        Err(e) => {
+            println!("unexpected error: {}. {}", e, "aborting amend".bold());
+            return Ok(());
+        }
+    };
+
+    let is_head = commits.first() == Some(&target);
+    let target_hash = target
+        .split_whitespace()
+        .next()
+        .unwrap_or("HEAD")
+        .to_string();
+
+    let edit_message = Confirm::new("edit the commit message?")
+        .with_default(false)
+        .prompt()
+        .unwrap_or(false);
+
+    if dry_run {
+        if is_head {
+            println!(
+                "would stage working changes and run: git commit --amend{}",
+                if edit_message { "" } else { " --no-edit" }
+            );
+        } else {
+            let fixup_flag = if edit_message { "--squash" } else { "--fixup" };
+            println!(
+                "would create a {} commit for {} and run: git rebase --autosquash -i {}^",
+                fixup_flag, target_hash, target_hash
+            );
+        }
+        return Ok(());
+    }
+
+    println!("{}", "staging working changes".bold());
+    vcs_exec(Backend::Git, &["add", "."], "failed to stage local changes", false)?;
+
+    if is_head {
+        let mut args = vec!["commit", "--amend"];
+        if !edit_message {
+            args.push("--no-edit");
+        }
+        vcs_exec(Backend::Git, &args, "failed to amend commit", false)?;
+    } else {
+        let before_head = git_query(&["rev-parse", "HEAD"], "failed to read current commit")?;
+        let before_hash = String::from_utf8_lossy(&before_head.stdout).trim().to_string();
+
+        // '--squash' keeps the combined message open for editing on autosquash;
+        // '--fixup' discards it, so autosquash can run unattended via the no-op editor
+        let fixup_flag = if edit_message { "--squash" } else { "--fixup" };
+        vcs_exec(
+            Backend::Git,
+            &["commit", fixup_flag, &target_hash],
+            "failed to create fixup commit",
+            false,
+        )?;
+
+        let fixup_head = git_query(&["rev-parse", "HEAD"], "failed to read fixup commit")?;
+        let fixup_hash = String::from_utf8_lossy(&fixup_head.stdout).trim().to_string();
+
+        let parent_rev = format!("{}^", target_hash);
+        let rebase = if edit_message {
+            vcs_exec(
+                Backend::Git,
+                &["rebase", "--autosquash", "-i", &parent_rev],
+                "failed to rebase",
+                false,
+            )?
+        } else {
+            vcs_exec(
+                Backend::Git,
+                &["-c", "sequence.editor=:", "rebase", "--autosquash", "-i", &parent_rev],
+                "failed to rebase",
+                false,
+            )?
+        };
+
+        if !rebase.status.success() {
+            println!("{}", "rebase failed. restoring pre-amend state".bold());
+            vcs_exec(Backend::Git, &["rebase", "--abort"], "failed to abort rebase", false)?;
+            vcs_exec(
+                Backend::Git,
+                &["reset", "--hard", &before_hash],
+                "failed to restore pre-amend state",
+                false,
+            )?;
+            println!(
+                "- your working changes are preserved in commit {}. recover them with: git cherry-pick {}",
+                fixup_hash.dimmed(),
+                fixup_hash
+            );
+            return Ok(());
+        }
+    }
+
+    println!("{}", "amend complete ^.^".bold());
+
+    Ok(())
+}